@@ -1,3 +1,5 @@
+mod icons;
+
 use std::{
     io,
     path::{Path, PathBuf},
@@ -29,18 +31,129 @@ struct Editor {
     content: text_editor::Content,
     error: Option<Error>,
     theme: iced::highlighter::Theme,
+    language: Option<String>,
+    language_options: Vec<String>,
+    icons: icons::Icons,
+    app_theme: AppTheme,
+    system_dark: bool,
+    timestamps: bool,
+    session_start: Option<std::time::Instant>,
+    timestamp_format: String,
     is_dirty: bool,
+    pending: Option<PendingAction>,
+}
+
+/// Default template for timestamp-mode stamps. `{mm}` and `{ss}` expand to the
+/// zero-padded minutes and seconds elapsed since the mode was enabled.
+const TIMESTAMP_FORMAT: &str = "[{mm}:{ss}] ";
+
+/// The palette used for the window chrome, kept independent of the syntax
+/// highlighter theme. `System` follows the current OS appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppTheme {
+    System,
+    Light,
+    Dark,
+}
+
+impl AppTheme {
+    const ALL: &'static [AppTheme] = &[AppTheme::System, AppTheme::Light, AppTheme::Dark];
 }
 
+impl std::fmt::Display for AppTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AppTheme::System => "System",
+            AppTheme::Light => "Light",
+            AppTheme::Dark => "Dark",
+        })
+    }
+}
+
+/// Syntaxes the bundled highlighter can tokenise, offered for manual override.
+const LANGUAGES: &[&str] = &[
+    "rs", "toml", "md", "txt", "py", "js", "ts", "json", "html", "css", "c", "cpp", "go", "sh",
+    "yaml", "xml",
+];
+
+/// Pick-list entry that clears the manual override and restores path detection.
+const AUTO_LANGUAGE: &str = "Auto";
+
 #[derive(Debug, Clone)]
 enum Message {
     Edit(text_editor::Action),
     Open,
     New,
     Save,
+    ConfirmDiscard(PendingAction, Confirmation),
     FileSaved(Result<PathBuf, Error>),
     FileOpened(Result<(PathBuf, Arc<String>), Error>),
     ThemeSelected(iced::highlighter::Theme),
+    LanguageSelected(String),
+    AppThemeSelected(AppTheme),
+    SystemThemeChanged(bool),
+    ToggleTimestamps,
+}
+
+/// An action that is blocked on the user deciding what to do with unsaved edits.
+#[derive(Debug, Clone, Copy)]
+enum PendingAction {
+    New,
+    Open,
+}
+
+/// The outcome of the "unsaved changes" confirmation dialog.
+#[derive(Debug, Clone, Copy)]
+enum Confirmation {
+    Save,
+    Discard,
+    Cancel,
+}
+
+impl Editor {
+    /// Run `action` immediately, or ask the user what to do first when there
+    /// are unsaved edits on screen.
+    fn guard(&mut self, action: PendingAction) -> Command<Message> {
+        if self.is_dirty {
+            Command::perform(confirm_discard(), move |confirmation| {
+                Message::ConfirmDiscard(action, confirmation)
+            })
+        } else {
+            self.proceed(action)
+        }
+    }
+
+    /// Carry out a [`PendingAction`] once it is safe to discard the buffer.
+    fn proceed(&mut self, action: PendingAction) -> Command<Message> {
+        match action {
+            PendingAction::New => {
+                self.path = None;
+                self.content = text_editor::Content::new();
+                self.error = None;
+                self.is_dirty = true;
+                Command::none()
+            }
+            PendingAction::Open => Command::perform(pick_file(), Message::FileOpened),
+        }
+    }
+
+    /// Render a named icon with the active flavor.
+    fn icon(&self, name: &str) -> Element<'_, Message> {
+        let icon = self.icons.resolve(name);
+        text(icon.glyph).font(icon.font).into()
+    }
+
+    /// Expand [`Editor::timestamp_format`] against the elapsed session time.
+    fn format_timestamp(&self) -> String {
+        let elapsed = self
+            .session_start
+            .map(|start| start.elapsed().as_secs())
+            .unwrap_or(0);
+
+        self.timestamp_format
+            .replace("{mm}", &format!("{:02}", elapsed / 60))
+            .replace("{ss}", &format!("{:02}", elapsed % 60))
+    }
 }
 
 impl Application for Editor {
@@ -56,7 +169,19 @@ impl Application for Editor {
                 content: text_editor::Content::with(include_str!("main.rs")),
                 error: None,
                 theme: iced::highlighter::Theme::SolarizedDark,
+                language: None,
+                language_options: std::iter::once(AUTO_LANGUAGE)
+                    .chain(LANGUAGES.iter().copied())
+                    .map(String::from)
+                    .collect(),
+                icons: load_icons(),
+                app_theme: AppTheme::System,
+                system_dark: detect_system_dark(),
+                timestamps: false,
+                session_start: None,
+                timestamp_format: TIMESTAMP_FORMAT.to_string(),
                 is_dirty: true,
+                pending: None,
             },
             Command::perform(load_file(default_file()), Message::FileOpened),
         )
@@ -71,7 +196,22 @@ impl Application for Editor {
             Message::Edit(action) => {
                 self.is_dirty = self.is_dirty || action.is_edit();
                 self.error = None;
+
+                let inserts_line_break =
+                    matches!(&action, text_editor::Action::Edit(text_editor::Edit::Enter));
+
                 self.content.edit(action);
+
+                // In timestamp mode, prefix every freshly opened line with a
+                // wall-clock stamp relative to when the mode was switched on.
+                if self.timestamps && inserts_line_break {
+                    let stamp = self.format_timestamp();
+                    self.content
+                        .edit(text_editor::Action::Edit(text_editor::Edit::Paste(
+                            Arc::new(stamp),
+                        )));
+                }
+
                 Command::none()
             }
             Message::FileOpened(Ok((path, content))) => {
@@ -84,13 +224,15 @@ impl Application for Editor {
                 self.error = Some(error);
                 Command::none()
             }
-            Message::Open => Command::perform(pick_file(), Message::FileOpened),
-            Message::New => {
-                self.path = None;
-                self.content = text_editor::Content::new();
-                self.is_dirty = true;
-                Command::none()
+            Message::Open => self.guard(PendingAction::Open),
+            Message::New => self.guard(PendingAction::New),
+            Message::ConfirmDiscard(action, Confirmation::Discard) => self.proceed(action),
+            Message::ConfirmDiscard(action, Confirmation::Save) => {
+                self.pending = Some(action);
+                let text = self.content.text();
+                Command::perform(save_file(self.path.clone(), text), Message::FileSaved)
             }
+            Message::ConfirmDiscard(_, Confirmation::Cancel) => Command::none(),
             Message::Save => {
                 let text = self.content.text();
                 Command::perform(save_file(self.path.clone(), text), Message::FileSaved)
@@ -98,9 +240,14 @@ impl Application for Editor {
             Message::FileSaved(Ok(path)) => {
                 self.path = Some(path);
                 self.is_dirty = false;
-                Command::none()
+                if let Some(action) = self.pending.take() {
+                    self.proceed(action)
+                } else {
+                    Command::none()
+                }
             }
             Message::FileSaved(Err(error)) => {
+                self.pending = None;
                 self.error = Some(error);
                 Command::none()
             }
@@ -108,31 +255,79 @@ impl Application for Editor {
                 self.theme = theme;
                 Command::none()
             }
+            Message::LanguageSelected(language) => {
+                self.language = (language != AUTO_LANGUAGE).then_some(language);
+                Command::none()
+            }
+            Message::AppThemeSelected(app_theme) => {
+                self.app_theme = app_theme;
+                Command::none()
+            }
+            Message::SystemThemeChanged(is_dark) => {
+                self.system_dark = is_dark;
+                Command::none()
+            }
+            Message::ToggleTimestamps => {
+                self.timestamps = !self.timestamps;
+                self.session_start = self.timestamps.then(std::time::Instant::now);
+                Command::none()
+            }
         }
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        iced::keyboard::on_key_press(|key_code, modifiers| match key_code {
+        let keys = iced::keyboard::on_key_press(|key_code, modifiers| match key_code {
             keyboard::KeyCode::S if modifiers.command() => Some(Message::Save),
             _ => None,
-        })
+        });
+
+        // Re-poll the OS appearance so `System` mode follows changes made while
+        // the editor is running. `dark_light::detect` can shell out to the
+        // desktop on Linux, so poll sparingly for a value that rarely changes.
+        let system = iced::time::every(std::time::Duration::from_secs(30))
+            .map(|_| Message::SystemThemeChanged(detect_system_dark()));
+
+        iced::Subscription::batch([keys, system])
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
         let controls = row!(
-            action(new_icon(), "New file", Some(Message::New)),
-            action(open_icon(), "Open file", Some(Message::Open)),
+            action(self.icon("new"), "New file", Some(Message::New)),
+            action(self.icon("open"), "Open file", Some(Message::Open)),
             action(
-                save_icon(),
+                self.icon("save"),
                 "Save File",
                 self.is_dirty.then_some(Message::Save)
             ),
+            action(
+                self.icon("timestamp"),
+                if self.timestamps {
+                    "Timestamp mode: on"
+                } else {
+                    "Timestamp mode: off"
+                },
+                Some(Message::ToggleTimestamps)
+            ),
             horizontal_space(Length::Fill),
+            pick_list(
+                self.language_options.as_slice(),
+                Some(
+                    self.language
+                        .clone()
+                        .unwrap_or_else(|| AUTO_LANGUAGE.to_string())
+                ),
+                Message::LanguageSelected
+            ),
             pick_list(
                 iced::highlighter::Theme::ALL,
                 Some(self.theme),
                 Message::ThemeSelected
             ),
+            pick_list(
+                AppTheme::ALL,
+                Some(self.app_theme),
+                Message::AppThemeSelected
+            ),
         )
         .spacing(10);
 
@@ -141,12 +336,13 @@ impl Application for Editor {
             .highlight::<Highlighter>(
                 iced::highlighter::Settings {
                     theme: self.theme,
-                    extension: self
-                        .path
-                        .as_ref()
-                        .and_then(|path| path.extension()?.to_str())
-                        .unwrap_or("rs")
-                        .to_string(),
+                    extension: self.language.clone().unwrap_or_else(|| {
+                        self.path
+                            .as_ref()
+                            .and_then(|path| path.extension()?.to_str())
+                            .unwrap_or("rs")
+                            .to_string()
+                    }),
                 },
                 |highlight, _theme| highlight.to_format(),
             );
@@ -166,7 +362,15 @@ impl Application for Editor {
                 text(format!("{}:{}", line + 1, column + 1))
             };
 
-            row![status, horizontal_space(Length::Fill), position]
+            let file_icon = {
+                let extension = self
+                    .path
+                    .as_ref()
+                    .and_then(|path| path.extension()?.to_str());
+                self.icon(icons::file_type(extension))
+            };
+
+            row![file_icon, status, horizontal_space(Length::Fill), position].spacing(5)
         };
 
         container(column![controls, input, status_bar].spacing(10))
@@ -175,7 +379,13 @@ impl Application for Editor {
     }
 
     fn theme(&self) -> iced::Theme {
-        if self.theme.is_dark() {
+        let dark = match self.app_theme {
+            AppTheme::System => self.system_dark,
+            AppTheme::Light => false,
+            AppTheme::Dark => true,
+        };
+
+        if dark {
             iced::Theme::Dark
         } else {
             iced::Theme::Light
@@ -205,21 +415,19 @@ fn action<'a>(
     .into()
 }
 
-fn new_icon<'a>() -> Element<'a, Message> {
-    icon('\u{E800}')
-}
+async fn confirm_discard() -> Confirmation {
+    let result = rfd::AsyncMessageDialog::new()
+        .set_title("Unsaved changes")
+        .set_description("You have unsaved changes. Save them before continuing?")
+        .set_buttons(rfd::MessageButtons::YesNoCancel)
+        .show()
+        .await;
 
-fn save_icon<'a>() -> Element<'a, Message> {
-    icon('\u{E801}')
-}
-
-fn open_icon<'a>() -> Element<'a, Message> {
-    icon('\u{F115}')
-}
-
-fn icon<'a, Message>(codepoint: char) -> Element<'a, Message> {
-    const ICON_FONT: Font = Font::with_name("editor-icons");
-    text(codepoint).font(ICON_FONT).into()
+    match result {
+        rfd::MessageDialogResult::Yes => Confirmation::Save,
+        rfd::MessageDialogResult::No => Confirmation::Discard,
+        _ => Confirmation::Cancel,
+    }
 }
 
 async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error> {
@@ -241,6 +449,23 @@ async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error
     Ok(path)
 }
 
+fn detect_system_dark() -> bool {
+    matches!(dark_light::detect(), dark_light::Mode::Dark)
+}
+
+fn load_icons() -> icons::Icons {
+    let mut registry = icons::Icons::new();
+
+    let flavors_file = PathBuf::from(format!("{}/icons.toml", env!("CARGO_MANIFEST_DIR")));
+    if let Ok(source) = std::fs::read_to_string(&flavors_file) {
+        if let Err(error) = registry.load(&source) {
+            eprintln!("ignoring {}: {error}", flavors_file.display());
+        }
+    }
+
+    registry
+}
+
 fn default_file() -> PathBuf {
     PathBuf::from(format!("{}/src/main.rs", env!("CARGO_MANIFEST_DIR")))
 }